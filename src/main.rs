@@ -1,8 +1,17 @@
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use git2::{BranchType, Repository, StatusOptions};
 use rayon::prelude::*;
+use serde::Serialize;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Directory names always skipped during discovery, in addition to whatever
+/// the user passes via `--exclude`.
+const DEFAULT_EXCLUDES: &[&str] = &["node_modules", "target", ".cache"];
 
 #[derive(Parser)]
 #[command(about = "List git repos, their dirty status, and whether they're local-only")]
@@ -14,6 +23,11 @@ struct Args {
     #[arg(short = 'L', default_value = "3")]
     depth: usize,
 
+    /// Glob(s) matched against directory names to skip during discovery,
+    /// in addition to the built-in defaults (node_modules, target, .cache)
+    #[arg(long)]
+    exclude: Vec<String>,
+
     /// Only show dirty repos
     #[arg(short, long)]
     dirty: bool,
@@ -29,45 +43,175 @@ struct Args {
     #[arg(long)]
     unpushed: bool,
 
+    /// Only show repos that are behind their upstream
+    #[arg(long)]
+    behind: bool,
+
+    /// Only show repos with stashed changes
+    #[arg(long)]
+    stashed: bool,
+
     /// Raw output for piping (one path per line)
-    #[arg(short, long)]
+    #[arg(short, long, conflicts_with = "json")]
     raw: bool,
+
+    /// Emit results as a JSON array instead of decorated text
+    #[arg(long, conflicts_with = "raw")]
+    json: bool,
+
+    /// Status backend to use for collecting dirty/ahead/behind info
+    #[arg(long, value_enum, default_value_t = StatusBackend::Libgit2)]
+    backend: StatusBackend,
+
+    /// Fetch the upstream remote before computing ahead/behind counts
+    #[arg(long)]
+    fetch: bool,
+
+    /// Give up on a fetch after this many seconds rather than block the scan.
+    /// With `--backend git` this only aborts an HTTP(S) transfer that stalls
+    /// for this long; other remotes are not bounded.
+    #[arg(long, default_value = "10")]
+    fetch_timeout: u64,
+
+    /// Only show repos whose HEAD commit is older than this (e.g. `30d`, `6mo`)
+    #[arg(long, value_parser = parse_duration_secs)]
+    stale: Option<i64>,
+
+    /// Sort results by path (default) or by commit age, oldest first
+    #[arg(long, value_enum, default_value_t = SortKey::Path)]
+    sort: SortKey,
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum SortKey {
+    Path,
+    Age,
+}
+
+/// Parses durations like `30d`, `6mo`, `2w`, `1y` into a second count.
+/// A month is approximated as 30 days.
+fn parse_duration_secs(s: &str) -> Result<i64, String> {
+    let (num, unit) = s
+        .find(|c: char| !c.is_ascii_digit())
+        .map(|i| s.split_at(i))
+        .ok_or_else(|| format!("invalid duration '{s}', expected e.g. '30d' or '6mo'"))?;
+    let num: i64 = num.parse().map_err(|_| format!("invalid duration '{s}'"))?;
+    let day = 86_400;
+    let secs_per_unit = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => day,
+        "w" => 7 * day,
+        "mo" => 30 * day,
+        "y" => 365 * day,
+        _ => return Err(format!("unknown duration unit '{unit}' in '{s}'")),
+    };
+    Ok(num * secs_per_unit)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum StatusBackend {
+    /// Walk the working tree in-process via libgit2 (portable, default)
+    Libgit2,
+    /// Shell out to the native `git` binary, faster on very large repos
+    Git,
+}
+
+#[derive(Serialize)]
 struct RepoInfo {
     path: PathBuf,
     dirty: bool,
     local_only: bool,
     ahead: Option<usize>,
+    behind: Option<usize>,
+    branch: Option<String>,
+    stash_count: usize,
+    commit_time: Option<i64>,
+}
+
+/// Formats a second count as a coarse relative age, e.g. "3mo ago" or "2d ago".
+fn format_age(seconds_ago: i64) -> String {
+    let day = 86_400;
+    match seconds_ago {
+        s if s < 60 => "just now".to_string(),
+        s if s < 3600 => format!("{}m ago", s / 60),
+        s if s < day => format!("{}h ago", s / 3600),
+        s if s < 30 * day => format!("{}d ago", s / day),
+        s if s < 365 * day => format!("{}mo ago", s / (30 * day)),
+        s => format!("{}y ago", s / (365 * day)),
+    }
+}
+
+/// Matches a shell-style glob (`*` and `?` wildcards) against `text`.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    // Iterative two-pointer matcher (no backtracking recursion), so a
+    // pattern with several '*' wildcards can't blow up combinatorially.
+    let (mut pi, mut ti) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while ti < text.len() {
+        if pi < pattern.len() && (pattern[pi] == '?' || pattern[pi] == text[ti]) {
+            pi += 1;
+            ti += 1;
+        } else if pi < pattern.len() && pattern[pi] == '*' {
+            star = Some((pi, ti));
+            pi += 1;
+        } else if let Some((star_pi, star_ti)) = star {
+            pi = star_pi + 1;
+            ti = star_ti + 1;
+            star = Some((star_pi, ti));
+        } else {
+            return false;
+        }
+    }
+    while pi < pattern.len() && pattern[pi] == '*' {
+        pi += 1;
+    }
+    pi == pattern.len()
 }
 
-fn find_repos(base: &Path, max_depth: usize) -> Vec<PathBuf> {
-    let mut repos = Vec::new();
-    collect_repos(base, max_depth, 0, &mut repos);
+fn is_excluded(path: &Path, excludes: &[String]) -> bool {
+    let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+        return false;
+    };
+    excludes.iter().any(|pattern| glob_match(pattern, name))
+}
+
+fn find_repos(base: &Path, max_depth: usize, excludes: &[String]) -> Vec<PathBuf> {
+    let mut repos = collect_repos(base, max_depth, 0, excludes);
     repos.sort();
+    repos.dedup();
     repos
 }
 
-fn collect_repos(dir: &Path, max_depth: usize, depth: usize, repos: &mut Vec<PathBuf>) {
+/// Scans subtrees concurrently via rayon, stopping at the first `.git` found
+/// and skipping symlinked directories and any name matching `excludes`.
+fn collect_repos(dir: &Path, max_depth: usize, depth: usize, excludes: &[String]) -> Vec<PathBuf> {
     if depth > max_depth {
-        return;
+        return Vec::new();
     }
     if dir.join(".git").exists() {
-        repos.push(dir.to_path_buf());
-        return;
+        return vec![dir.to_path_buf()];
     }
     let Ok(entries) = fs::read_dir(dir) else {
-        return;
+        return Vec::new();
     };
-    for entry in entries.flatten() {
-        let path = entry.path();
-        if path.is_dir() && !path.is_symlink() {
-            collect_repos(&path, max_depth, depth + 1, repos);
-        }
-    }
+    let subdirs: Vec<PathBuf> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_dir() && !p.is_symlink() && !is_excluded(p, excludes))
+        .collect();
+
+    subdirs
+        .par_iter()
+        .flat_map(|p| collect_repos(p, max_depth, depth + 1, excludes))
+        .collect()
 }
 
-fn ahead_of_upstream(repo: &Repository) -> Option<usize> {
+fn ahead_behind_upstream(repo: &Repository) -> Option<(usize, usize)> {
     // Detached HEAD or unborn branch will fail here.
     let head = repo.head().ok()?;
     let head_oid = head.target()?;
@@ -80,13 +224,77 @@ fn ahead_of_upstream(repo: &Repository) -> Option<usize> {
     let upstream_ref = upstream.get();
     let upstream_oid = upstream_ref.target()?;
 
-    // ahead/behind count vs upstream
-    let (ahead, _behind) = repo.graph_ahead_behind(head_oid, upstream_oid).ok()?;
-    Some(ahead)
+    repo.graph_ahead_behind(head_oid, upstream_oid).ok()
+}
+
+fn stash_count(repo: &mut Repository) -> usize {
+    let mut count = 0;
+    let _ = repo.stash_foreach(|_, _, _| {
+        count += 1;
+        true
+    });
+    count
+}
+
+/// Seconds-since-epoch of the commit at HEAD. `None` for an unborn branch
+/// or a detached HEAD that fails to peel to a commit.
+fn commit_time(repo: &Repository) -> Option<i64> {
+    let head = repo.head().ok()?;
+    let commit = head.peel_to_commit().ok()?;
+    Some(commit.time().seconds())
+}
+
+/// Fetch the current branch's upstream remote via libgit2, enforcing a hard
+/// wall-clock deadline. libgit2 only invokes `transfer_progress` once pack
+/// data starts streaming in, so a callback-only deadline never fires while
+/// stuck connecting or negotiating refs against an unresponsive remote. To
+/// bound that too, the fetch runs on its own thread and the caller simply
+/// stops waiting at `timeout`; a remote that never responds leaves that
+/// thread to finish (or hang) in the background rather than blocking the scan.
+fn fetch_libgit2(path: &Path, timeout: Duration) -> Option<()> {
+    let path = path.to_path_buf();
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let result = (|| -> Option<()> {
+            let repo = Repository::open(&path).ok()?;
+            let head = repo.head().ok()?;
+            let name = head.shorthand()?;
+            let remote_name = repo.branch_upstream_remote(&format!("refs/heads/{name}")).ok()?;
+            let mut remote = repo.find_remote(remote_name.as_str()?).ok()?;
+            remote.fetch(&[] as &[&str], None, None).ok()
+        })();
+        // The receiver may already be gone if we missed the deadline.
+        let _ = tx.send(result);
+    });
+    rx.recv_timeout(timeout).ok().flatten()
 }
 
-fn inspect_repo(path: &Path, compute_unpushed: bool) -> Option<RepoInfo> {
-    let repo = Repository::open(path).ok()?;
+/// Fetch via the native `git` binary, bounding a stalled HTTP(S) transfer
+/// with a low-speed cutoff instead of the thread deadline of `fetch_libgit2`.
+fn fetch_git(path: &Path, timeout: Duration) -> Option<()> {
+    let secs = timeout.as_secs().max(1).to_string();
+    let status = Command::new("git")
+        .args(["-c", "http.lowSpeedLimit=1", "-c", &format!("http.lowSpeedTime={secs}"), "fetch"])
+        .current_dir(path)
+        .status()
+        .ok()?;
+    status.success().then_some(())
+}
+
+fn inspect_repo(
+    path: &Path,
+    compute_unpushed: bool,
+    backend: StatusBackend,
+    fetch: Option<Duration>,
+) -> Option<RepoInfo> {
+    match backend {
+        StatusBackend::Libgit2 => inspect_repo_libgit2(path, compute_unpushed, fetch),
+        StatusBackend::Git => inspect_repo_git(path, compute_unpushed, fetch),
+    }
+}
+
+fn inspect_repo_libgit2(path: &Path, compute_unpushed: bool, fetch: Option<Duration>) -> Option<RepoInfo> {
+    let mut repo = Repository::open(path).ok()?;
 
     let mut opts = StatusOptions::new();
     opts.include_untracked(true)
@@ -94,11 +302,18 @@ fn inspect_repo(path: &Path, compute_unpushed: bool) -> Option<RepoInfo> {
         .exclude_submodules(true);
     let dirty = !repo.statuses(Some(&mut opts)).ok()?.is_empty();
     let local_only = repo.remotes().ok().is_none_or(|r| r.is_empty());
-
-    let ahead = if compute_unpushed {
-        ahead_of_upstream(&repo)
+    let branch = repo.head().ok().and_then(|h| h.shorthand().map(String::from));
+    let stash_count = stash_count(&mut repo);
+    let commit_time = commit_time(&repo);
+
+    let (ahead, behind) = if compute_unpushed {
+        if let Some(timeout) = fetch {
+            // A failed or timed-out fetch just falls back to local refs.
+            let _ = fetch_libgit2(path, timeout);
+        }
+        ahead_behind_upstream(&repo).map_or((None, None), |(a, b)| (Some(a), Some(b)))
     } else {
-        None
+        (None, None)
     };
 
     Some(RepoInfo {
@@ -106,6 +321,67 @@ fn inspect_repo(path: &Path, compute_unpushed: bool) -> Option<RepoInfo> {
         dirty,
         local_only,
         ahead,
+        behind,
+        branch,
+        stash_count,
+        commit_time,
+    })
+}
+
+/// Status via a `git status --porcelain=v2 --branch` subprocess, which is
+/// substantially faster than a libgit2 working-tree walk on huge repos.
+fn inspect_repo_git(path: &Path, compute_unpushed: bool, fetch: Option<Duration>) -> Option<RepoInfo> {
+    if let Some(timeout) = fetch {
+        // A failed or timed-out fetch just falls back to local refs.
+        let _ = fetch_git(path, timeout);
+    }
+
+    let output = Command::new("git")
+        .args(["status", "--porcelain=v2", "--branch"])
+        .current_dir(path)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    let mut dirty = false;
+    let mut ahead = None;
+    let mut behind = None;
+    for line in stdout.lines() {
+        if let Some(ab) = line.strip_prefix("# branch.ab ") {
+            // Only surface ahead/behind when asked, matching the libgit2
+            // backend's schema so the JSON shape doesn't depend on --backend.
+            if compute_unpushed {
+                let mut parts = ab.split_whitespace();
+                let a = parts.next().and_then(|s| s.strip_prefix('+'));
+                let b = parts.next().and_then(|s| s.strip_prefix('-'));
+                ahead = a.and_then(|s| s.parse().ok());
+                behind = b.and_then(|s| s.parse().ok());
+            }
+        } else if matches!(line.chars().next(), Some('1' | '2' | '?' | 'u')) {
+            dirty = true;
+        }
+    }
+
+    // Remotes and stash are cheap compared to the status walk, so libgit2
+    // is still used for them regardless of the chosen status backend.
+    let mut repo = Repository::open(path).ok()?;
+    let local_only = repo.remotes().ok().is_none_or(|r| r.is_empty());
+    let branch = repo.head().ok().and_then(|h| h.shorthand().map(String::from));
+    let stash_count = stash_count(&mut repo);
+    let commit_time = commit_time(&repo);
+
+    Some(RepoInfo {
+        path: path.to_path_buf(),
+        dirty,
+        local_only,
+        ahead,
+        behind,
+        branch,
+        stash_count,
+        commit_time,
     })
 }
 
@@ -115,17 +391,35 @@ fn run(args: Args) -> Result<(), String> {
         .canonicalize()
         .map_err(|_| format!("dirty: cannot access '{}'", args.path.display()))?;
 
-    let repos = find_repos(&base, args.depth);
-    let infos: Vec<_> = repos
+    let compute_upstream = args.unpushed || args.behind;
+    let fetch = (args.fetch && compute_upstream).then(|| Duration::from_secs(args.fetch_timeout));
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map_err(|e| e.to_string())?.as_secs() as i64;
+    let excludes: Vec<String> =
+        DEFAULT_EXCLUDES.iter().map(|s| s.to_string()).chain(args.exclude.iter().cloned()).collect();
+    let repos = find_repos(&base, args.depth, &excludes);
+    let mut infos: Vec<_> = repos
         .par_iter()
-        .filter_map(|p| inspect_repo(p, args.unpushed))
+        .filter_map(|p| inspect_repo(p, compute_upstream, args.backend, fetch))
         .filter(|i| {
             (!args.dirty || i.dirty)
                 && (!args.local || i.local_only)
                 && (!args.unpushed || i.ahead.unwrap_or(0) > 0)
+                && (!args.behind || i.behind.unwrap_or(0) > 0)
+                && (!args.stashed || i.stash_count > 0)
+                && args.stale.is_none_or(|max_age| i.commit_time.is_some_and(|t| now - t >= max_age))
         })
         .collect();
 
+    match args.sort {
+        SortKey::Path => infos.sort_by(|a, b| a.path.cmp(&b.path)),
+        SortKey::Age => infos.sort_by(|a, b| match (a.commit_time, b.commit_time) {
+            (Some(x), Some(y)) => x.cmp(&y),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => std::cmp::Ordering::Equal,
+        }),
+    }
+
     if infos.is_empty() {
         return Err(if repos.is_empty() {
             format!("No git repos found in {}", base.display())
@@ -134,6 +428,25 @@ fn run(args: Args) -> Result<(), String> {
         });
     }
 
+    if args.json {
+        let infos: Vec<_> = infos
+            .iter()
+            .map(|i| RepoInfo {
+                path: i.path.strip_prefix(&base).unwrap_or(&i.path).to_path_buf(),
+                dirty: i.dirty,
+                local_only: i.local_only,
+                ahead: i.ahead,
+                behind: i.behind,
+                branch: i.branch.clone(),
+                stash_count: i.stash_count,
+                commit_time: i.commit_time,
+            })
+            .collect();
+        let json = serde_json::to_string_pretty(&infos).map_err(|e| e.to_string())?;
+        println!("{json}");
+        return Ok(());
+    }
+
     for info in &infos {
         let rel = info.path.strip_prefix(&base).unwrap_or(&info.path).display();
         if args.raw {
@@ -141,14 +454,29 @@ fn run(args: Args) -> Result<(), String> {
         } else {
             let dirty = if info.dirty { "\x1b[31m*\x1b[0m" } else { " " };
             let local = if info.local_only { " \x1b[33m[local]\x1b[0m" } else { "" };
-            let unpushed = if args.unpushed {
-                let n = info.ahead.unwrap_or(0);
+            let branch = info
+                .branch
+                .as_deref()
+                .map(|b| format!(" \x1b[36m({b})\x1b[0m"))
+                .unwrap_or_default();
+            let ahead_behind = if compute_upstream {
+                let a = info.ahead.unwrap_or(0);
+                let b = info.behind.unwrap_or(0);
                 // blue
-                format!(" \x1b[34m[↑{n}]\x1b[0m")
+                format!(" \x1b[34m[↑{a} ↓{b}]\x1b[0m")
+            } else {
+                String::new()
+            };
+            let stash = if info.stash_count > 0 {
+                format!(" \x1b[35m[stash:{}]\x1b[0m", info.stash_count)
             } else {
                 String::new()
             };
-            println!(" {dirty} {rel}{local}{unpushed}");
+            let age = info
+                .commit_time
+                .map(|t| format!(" \x1b[90m({})\x1b[0m", format_age(now - t)))
+                .unwrap_or_default();
+            println!(" {dirty} {rel}{branch}{local}{ahead_behind}{stash}{age}");
         }
     }
 
@@ -203,8 +531,8 @@ mod tests {
         setup_repo(tmp.path(), "a", false, true);
         setup_repo(tmp.path(), "deep/nested/b", false, true);
 
-        assert_eq!(find_repos(tmp.path(), 1).len(), 1);
-        assert_eq!(find_repos(tmp.path(), 3).len(), 2);
+        assert_eq!(find_repos(tmp.path(), 1, &[]).len(), 1);
+        assert_eq!(find_repos(tmp.path(), 3, &[]).len(), 2);
     }
 
     #[test]
@@ -213,8 +541,8 @@ mod tests {
         let clean = setup_repo(tmp.path(), "clean", false, true);
         let dirty = setup_repo(tmp.path(), "dirty", true, true);
 
-        assert!(!inspect_repo(&clean, false).unwrap().dirty);
-        assert!(inspect_repo(&dirty, false).unwrap().dirty);
+        assert!(!inspect_repo(&clean, false, StatusBackend::Libgit2, None).unwrap().dirty);
+        assert!(inspect_repo(&dirty, false, StatusBackend::Libgit2, None).unwrap().dirty);
     }
 
     #[test]
@@ -223,8 +551,99 @@ mod tests {
         let with_remote = setup_repo(tmp.path(), "remote", false, true);
         let no_remote = setup_repo(tmp.path(), "local", false, false);
 
-        assert!(!inspect_repo(&with_remote, false).unwrap().local_only);
-        assert!(inspect_repo(&no_remote, false).unwrap().local_only);
+        assert!(!inspect_repo(&with_remote, false, StatusBackend::Libgit2, None).unwrap().local_only);
+        assert!(inspect_repo(&no_remote, false, StatusBackend::Libgit2, None).unwrap().local_only);
+    }
+
+    #[test]
+    fn inspect_git_backend_detects_dirty() {
+        let tmp = tempfile::tempdir().unwrap();
+        let clean = setup_repo(tmp.path(), "clean", false, true);
+        let dirty = setup_repo(tmp.path(), "dirty", true, true);
+
+        assert!(!inspect_repo(&clean, false, StatusBackend::Git, None).unwrap().dirty);
+        assert!(inspect_repo(&dirty, false, StatusBackend::Git, None).unwrap().dirty);
+    }
+
+    #[test]
+    fn inspect_git_backend_handles_missing_upstream() {
+        let tmp = tempfile::tempdir().unwrap();
+        let no_remote = setup_repo(tmp.path(), "local", false, false);
+        let not_a_repo = tmp.path().join("not-a-repo");
+        fs::create_dir_all(&not_a_repo).unwrap();
+
+        let info = inspect_repo(&no_remote, true, StatusBackend::Git, None).unwrap();
+        assert_eq!(info.ahead, None);
+        assert_eq!(info.behind, None);
+
+        assert!(inspect_repo(&not_a_repo, false, StatusBackend::Git, None).is_none());
+    }
+
+    #[test]
+    fn inspect_git_backend_only_reports_ahead_when_asked() {
+        let tmp = tempfile::tempdir().unwrap();
+        let upstream = tmp.path().join("upstream");
+        Command::new("git")
+            .args(["init", "-q", "-b", "main", "--bare"])
+            .arg(&upstream)
+            .status()
+            .unwrap();
+
+        let clone = tmp.path().join("clone");
+        Command::new("git")
+            .args(["clone", "-q", "-c", "init.defaultBranch=main"])
+            .arg(&upstream)
+            .arg(&clone)
+            .status()
+            .unwrap();
+        Command::new("git")
+            .args(["commit", "--allow-empty", "-m", "init", "-q"])
+            .current_dir(&clone)
+            .status()
+            .unwrap();
+        Command::new("git").args(["push", "-q", "origin", "HEAD:main"]).current_dir(&clone).status().unwrap();
+        Command::new("git")
+            .args(["branch", "-q", "--set-upstream-to=origin/main"])
+            .current_dir(&clone)
+            .status()
+            .unwrap();
+        Command::new("git")
+            .args(["commit", "--allow-empty", "-m", "unpushed", "-q"])
+            .current_dir(&clone)
+            .status()
+            .unwrap();
+
+        let without_flag = inspect_repo(&clone, false, StatusBackend::Git, None).unwrap();
+        assert_eq!(without_flag.ahead, None);
+        assert_eq!(without_flag.behind, None);
+
+        let with_flag = inspect_repo(&clone, true, StatusBackend::Git, None).unwrap();
+        assert_eq!(with_flag.ahead, Some(1));
+        assert_eq!(with_flag.behind, Some(0));
+    }
+
+    #[test]
+    fn glob_match_handles_wildcards_and_pathological_patterns() {
+        assert!(glob_match("node_modules", "node_modules"));
+        assert!(glob_match("*.cache", "build.cache"));
+        assert!(glob_match("a*b*c", "aXXbYYc"));
+        assert!(!glob_match("a*b*c", "aXXbYY"));
+
+        // Many '*' wildcards used to blow up a recursive matcher; this
+        // should return (and fail to match) instantly.
+        let pattern = "*a*a*a*a*a*a*a*a*a*a*b";
+        let text = "a".repeat(30);
+        assert!(!glob_match(pattern, &text));
+    }
+
+    #[test]
+    fn find_repos_honors_exclude() {
+        let tmp = tempfile::tempdir().unwrap();
+        setup_repo(tmp.path(), "kept", false, true);
+        setup_repo(tmp.path(), "node_modules/dep", false, true);
+
+        assert_eq!(find_repos(tmp.path(), 3, &[]).len(), 2);
+        assert_eq!(find_repos(tmp.path(), 3, &["node_modules".to_string()]).len(), 1);
     }
 
     #[test]
@@ -233,6 +652,6 @@ mod tests {
         let parent = setup_repo(tmp.path(), "parent", false, true);
         fs::create_dir_all(parent.join("child/.git")).unwrap();
 
-        assert_eq!(find_repos(tmp.path(), 5).len(), 1);
+        assert_eq!(find_repos(tmp.path(), 5, &[]).len(), 1);
     }
 }